@@ -0,0 +1,456 @@
+//! Cut-and-choose batch challenges.
+//!
+//! A single [`crate::Challenge`] only gives the user a coin-flip's chance of catching a
+//! cheating device: either they challenge the one commitment they were given, or they don't.
+//! [`BatchChallenge`] runs the untrusted computation `n` independent times up front and lets
+//! the verifier challenge an arbitrary subset of them, leaving the rest eligible to be cast.
+//! This is the classic cut-and-choose construction used to drive the probability of an
+//! undetected cheat down to whatever bound the caller needs; see [`detection_probability`].
+
+use crate::{check_commitment, Error, PlaybackRng, RecordingRng};
+use digest::{Digest, FixedOutputReset};
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroize;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// One node on a Merkle inclusion path: the sibling hash and which side it sits on relative
+/// to the node being proven.
+pub struct MerkleNode {
+    pub hash: Vec<u8>,
+    pub is_left: bool,
+}
+
+/// The commitment produced by [`BatchChallenge::commit`]: a Merkle root over the `n`
+/// per-instance leaf hashes, plus the leaves themselves so a verifier holding only the root
+/// (e.g. scanned from a QR code) can still be handed the full set out-of-band if needed.
+pub struct BatchCommitment {
+    pub root: Vec<u8>,
+    pub leaves: Vec<Vec<u8>>,
+}
+
+/// The revealed randomness and opening proof for one challenged instance.
+pub struct RevealedInstance {
+    pub index: usize,
+    pub leaf: Vec<u8>,
+    pub recorded_random: Vec<u8>,
+    pub opening_path: Vec<MerkleNode>,
+}
+
+/// A cut-and-choose batch of `n` independent runs of an untrusted computation.
+pub struct BatchChallenge {
+    results: Vec<Vec<u8>>,
+    recorded: Vec<Vec<u8>>,
+    leaves: Vec<Vec<u8>>,
+    revealed: Vec<bool>,
+    committed: bool,
+}
+
+#[cfg(feature = "std")]
+impl BatchChallenge {
+    /// Run `computation` `n` independent times, each against its own [`RecordingRng`] so the
+    /// randomness streams used by each instance are kept separate.
+    ///
+    /// `computation` returns a `Result`, same as [`crate::Challenge`]'s, so a real failure
+    /// (e.g. an encryption error) can propagate instead of panicking mid-batch. If any
+    /// instance's computation returns `Err`, this zeroizes every result and recorded random
+    /// factor gathered so far (including the failing instance's partial randomness) and
+    /// returns `Error::Computation` without constructing a batch.
+    pub fn new<R: RngCore + CryptoRng, C, E>(rng: &mut R, n: usize, computation: C) -> Result<Self, Error<E>>
+    where
+        C: Fn(&mut RecordingRng<R>) -> Result<Vec<u8>, E>,
+    {
+        let mut results = Vec::with_capacity(n);
+        let mut recorded = Vec::with_capacity(n);
+        for _ in 0..n {
+            let mut recording_rng = RecordingRng::new(rng);
+            let computed = computation(&mut recording_rng);
+            let mut partial_random = recording_rng.fetch_recorded();
+
+            let result = match computed {
+                Ok(result) => result,
+                Err(e) => {
+                    results.zeroize();
+                    recorded.zeroize();
+                    partial_random.zeroize();
+                    return Err(Error::Computation(e));
+                }
+            };
+            results.push(result);
+            recorded.push(partial_random);
+        }
+        Ok(BatchChallenge {
+            results,
+            recorded,
+            leaves: Vec::new(),
+            revealed: vec![false; n],
+            committed: false,
+        })
+    }
+}
+
+/// `no_std` counterpart of [`BatchChallenge::new`]: same behavior, but against the
+/// fixed-capacity `N`-byte [`RecordingRng`] instead of the heap-growing one.
+#[cfg(not(feature = "std"))]
+impl BatchChallenge {
+    /// Run `computation` `n` independent times, each against its own [`RecordingRng`] so the
+    /// randomness streams used by each instance are kept separate.
+    ///
+    /// See the non-`no_std` [`BatchChallenge::new`] for the full behavior; this differs only
+    /// in draining each instance's fixed-capacity recording buffer into a heap-allocated
+    /// `Vec<u8>`, since `BatchChallenge` itself always needs `alloc`.
+    pub fn new<R: RngCore + CryptoRng, C, E, const N: usize>(rng: &mut R, n: usize, computation: C) -> Result<Self, Error<E>>
+    where
+        C: Fn(&mut RecordingRng<R, N>) -> Result<Vec<u8>, E>,
+    {
+        let mut results = Vec::with_capacity(n);
+        let mut recorded = Vec::with_capacity(n);
+        for _ in 0..n {
+            let mut recording_rng = RecordingRng::new(rng);
+            let computed = computation(&mut recording_rng);
+            let mut partial_random: Vec<u8> = recording_rng.fetch_recorded().iter().copied().collect();
+
+            let result = match computed {
+                Ok(result) => result,
+                Err(e) => {
+                    results.zeroize();
+                    recorded.zeroize();
+                    partial_random.zeroize();
+                    return Err(Error::Computation(e));
+                }
+            };
+            results.push(result);
+            recorded.push(partial_random);
+        }
+        Ok(BatchChallenge {
+            results,
+            recorded,
+            leaves: Vec::new(),
+            revealed: vec![false; n],
+            committed: false,
+        })
+    }
+}
+
+impl BatchChallenge {
+    /// Number of instances in this batch.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Whether this batch has no instances.
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Commit to all `n` instances, returning a Merkle root over their per-instance leaf
+    /// hashes along with the leaves themselves.
+    ///
+    /// Each leaf binds its instance's index as a public input (via [`check_commitment`]'s
+    /// transcript), so leaves cannot be reordered or replayed against a different slot.
+    pub fn commit<H: Digest + FixedOutputReset>(&mut self, hasher: &mut H) -> BatchCommitment {
+        self.leaves = self
+            .results
+            .iter()
+            .enumerate()
+            .map(|(index, result)| {
+                let mut transcript = crate::transcript::Transcript::new(hasher);
+                transcript.append("index", &(index as u64).to_le_bytes());
+                transcript.append("result", result);
+                transcript.finalize()
+            })
+            .collect();
+        self.committed = true;
+
+        BatchCommitment {
+            root: merkle_root(hasher, &self.leaves),
+            leaves: self.leaves.clone(),
+        }
+    }
+
+    /// Reveal the recorded randomness (and Merkle opening path) for an arbitrary subset of
+    /// instances, chosen by the verifier. The challenged instances are no longer eligible to
+    /// be cast via [`BatchChallenge::into_result`].
+    pub fn challenge<H: Digest + FixedOutputReset>(
+        &mut self,
+        hasher: &mut H,
+        indices: &[usize],
+    ) -> Vec<RevealedInstance> {
+        if !self.committed {
+            panic!("benaloh_challenge: BatchChallenge.commit() must be invoked before calling BatchChallenge.challenge()")
+        }
+        indices
+            .iter()
+            .map(|&index| {
+                self.revealed[index] = true;
+                self.results[index].zeroize();
+                let mut recorded_random = Vec::new();
+                core::mem::swap(&mut recorded_random, &mut self.recorded[index]);
+                RevealedInstance {
+                    index,
+                    leaf: self.leaves[index].clone(),
+                    recorded_random,
+                    opening_path: merkle_path(hasher, &self.leaves, index),
+                }
+            })
+            .collect()
+    }
+
+    /// Take the result of an un-challenged instance, discarding (zeroing) its recorded random
+    /// factors. Panics if `index` was revealed by [`BatchChallenge::challenge`], since casting
+    /// an instance whose randomness is known defeats the purpose of the challenge.
+    pub fn into_result(&mut self, index: usize) -> Vec<u8> {
+        if !self.committed {
+            panic!("benaloh_challenge: BatchChallenge.commit() must be invoked before calling BatchChallenge.into_result()")
+        }
+        if self.revealed[index] {
+            panic!("benaloh_challenge: instance {} was challenged and must not be cast", index)
+        }
+        self.recorded[index].zeroize();
+        let mut result = Vec::new();
+        core::mem::swap(&mut result, &mut self.results[index]);
+        result
+    }
+}
+
+/// Check a single revealed instance against a batch's Merkle root.
+///
+/// This should be done on a different device seperately from the device being challenged.
+/// Verification reuses [`check_commitment`] per revealed index against its leaf hash, then
+/// confirms that leaf is actually included under `root` via its opening path.
+pub fn check_batch_commitment<H: Digest + FixedOutputReset, C, E>(
+    hasher: &mut H,
+    root: &[u8],
+    revealed: &RevealedInstance,
+    untrusted_computation: C,
+) -> Result<(), Error<E>>
+where
+    C: Fn(&mut PlaybackRng) -> Result<Vec<u8>, E>,
+{
+    if !verify_opening(hasher, &revealed.leaf, &revealed.opening_path, root) {
+        return Err(Error::VerificationFailed);
+    }
+    let public_inputs: [(&str, &[u8]); 1] = [("index", &(revealed.index as u64).to_le_bytes())];
+    check_commitment(hasher, &revealed.leaf, &revealed.recorded_random, &public_inputs, untrusted_computation)
+}
+
+/// The probability that a device cheating on `k` of `n` batch instances is caught when the
+/// verifier challenges `challenged` of them at random: `1 - C(n-k, challenged) / C(n, challenged)`.
+///
+/// Callers use this to size `n` for a target assurance level. Note that for a device that
+/// cheats on a single instance (`k == 1`) this is just `challenged / n`, e.g. 0.25 when a
+/// quarter of the instances get challenged.
+///
+/// Panics if `k` or `challenged` is greater than `n`.
+pub fn detection_probability(n: usize, k: usize, challenged: usize) -> f64 {
+    assert!(k <= n, "can't cheat on more instances than exist");
+    assert!(challenged <= n, "can't challenge more instances than exist");
+    if n - k < challenged {
+        // There aren't enough honest instances left to fill every challenge slot, so at
+        // least one challenge must land on a cheated instance.
+        return 1.0;
+    }
+    // C(n-k, challenged) / C(n, challenged), computed as a running product to avoid
+    // overflowing factorials for realistic batch sizes.
+    let uncaught = (0..challenged).fold(1.0f64, |acc, i| acc * (n - k - i) as f64 / (n - i) as f64);
+    1.0 - uncaught
+}
+
+fn hash_leaf<H: Digest + FixedOutputReset>(hasher: &mut H, leaf: &[u8]) -> Vec<u8> {
+    Digest::update(hasher, [0x00]);
+    Digest::update(hasher, leaf);
+    hasher.finalize_fixed_reset().to_vec()
+}
+
+fn hash_node<H: Digest + FixedOutputReset>(hasher: &mut H, left: &[u8], right: &[u8]) -> Vec<u8> {
+    Digest::update(hasher, [0x01]);
+    Digest::update(hasher, left);
+    Digest::update(hasher, right);
+    hasher.finalize_fixed_reset().to_vec()
+}
+
+/// Build a Merkle tree over `leaves` level by level, duplicating the last node of any
+/// odd-sized level, and return just the root. Leaf and internal nodes are domain-separated
+/// (`0x00`/`0x01` prefixes) so a leaf can never be mistaken for an internal node.
+fn merkle_root<H: Digest + FixedOutputReset>(hasher: &mut H, leaves: &[Vec<u8>]) -> Vec<u8> {
+    let mut level: Vec<Vec<u8>> = leaves.iter().map(|leaf| hash_leaf(hasher, leaf)).collect();
+    if level.is_empty() {
+        return hash_leaf(hasher, &[]);
+    }
+    while level.len() > 1 {
+        if level.len() % 2 == 1 {
+            level.push(level.last().unwrap().clone());
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| hash_node(hasher, &pair[0], &pair[1]))
+            .collect();
+    }
+    level.remove(0)
+}
+
+/// Build the sibling path from leaf `index` up to the root.
+fn merkle_path<H: Digest + FixedOutputReset>(
+    hasher: &mut H,
+    leaves: &[Vec<u8>],
+    index: usize,
+) -> Vec<MerkleNode> {
+    let mut level: Vec<Vec<u8>> = leaves.iter().map(|leaf| hash_leaf(hasher, leaf)).collect();
+    let mut path = Vec::new();
+    let mut position = index;
+    while level.len() > 1 {
+        if level.len() % 2 == 1 {
+            level.push(level.last().unwrap().clone());
+        }
+        let sibling = position ^ 1;
+        path.push(MerkleNode {
+            hash: level[sibling].clone(),
+            is_left: sibling < position,
+        });
+        level = level
+            .chunks(2)
+            .map(|pair| hash_node(hasher, &pair[0], &pair[1]))
+            .collect();
+        position /= 2;
+    }
+    path
+}
+
+/// Recompute the root from `leaf` and its opening `path`, and compare against `root`.
+fn verify_opening<H: Digest + FixedOutputReset>(
+    hasher: &mut H,
+    leaf: &[u8],
+    path: &[MerkleNode],
+    root: &[u8],
+) -> bool {
+    let mut current = hash_leaf(hasher, leaf);
+    for node in path {
+        current = if node.is_left {
+            hash_node(hasher, &node.hash, &current)
+        } else {
+            hash_node(hasher, &current, &node.hash)
+        };
+    }
+    current == root.to_vec()
+}
+
+/// `no_std` counterpart of the tests below: [`BatchChallenge::new`]'s `no_std` form carries
+/// an extra const generic (the recording buffer's capacity `N`), so the closure here needs an
+/// explicit `RecordingRng<_, 8>` annotation to pin it, whereas the non-`no_std` tests below let
+/// it infer.
+#[cfg(all(test, not(feature = "std")))]
+mod no_std_tests {
+    use super::*;
+    use rand::Rng;
+    use sha2::Sha256;
+    use std::convert::Infallible;
+
+    fn untrusted_computation<R: Rng>(rng: &mut R) -> Result<Vec<u8>, Infallible> {
+        let mut bytes = vec![0; 8];
+        rng.fill_bytes(&mut bytes);
+        Ok(bytes)
+    }
+
+    #[test]
+    fn batch_challenge_test() -> Result<(), Error<Infallible>> {
+        let mut rng = rand::thread_rng();
+        let mut hasher = Sha256::new();
+
+        let mut batch = BatchChallenge::new(&mut rng, 10, |rng: &mut RecordingRng<_, 8>| {
+            untrusted_computation(rng)
+        })?;
+        let commitment = batch.commit(&mut hasher);
+
+        let revealed = batch.challenge(&mut hasher, &[1, 3, 5]);
+        for instance in &revealed {
+            check_batch_commitment(&mut hasher, &commitment.root, instance, |rng: _| {
+                untrusted_computation(rng)
+            })?;
+        }
+
+        let _cast = batch.into_result(0);
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use sha2::Sha256;
+    use std::convert::Infallible;
+
+    fn untrusted_computation<R: Rng>(rng: &mut R) -> Result<Vec<u8>, Infallible> {
+        let mut bytes = vec![0; 8];
+        rng.fill_bytes(&mut bytes);
+        Ok(bytes)
+    }
+
+    #[test]
+    fn batch_challenge_test() -> Result<(), Error<Infallible>> {
+        let mut rng = rand::thread_rng();
+        let mut hasher = Sha256::new();
+
+        let mut batch = BatchChallenge::new(&mut rng, 10, |rng: _| untrusted_computation(rng))?;
+        let commitment = batch.commit(&mut hasher);
+
+        let revealed = batch.challenge(&mut hasher, &[1, 3, 5]);
+        for instance in &revealed {
+            check_batch_commitment(&mut hasher, &commitment.root, instance, |rng: _| {
+                untrusted_computation(rng)
+            })?;
+        }
+
+        // Un-challenged instances are still eligible to be cast.
+        let _cast = batch.into_result(0);
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn cannot_cast_a_challenged_instance() {
+        let mut rng = rand::thread_rng();
+        let mut hasher = Sha256::new();
+
+        let mut batch = BatchChallenge::new(&mut rng, 4, |rng: _| untrusted_computation(rng)).unwrap();
+        batch.commit(&mut hasher);
+        batch.challenge(&mut hasher, &[2]);
+        batch.into_result(2);
+    }
+
+    #[test]
+    fn fallible_batch_computation_test() {
+        #[derive(Debug, thiserror::Error)]
+        #[error("encryption failed")]
+        struct MyError;
+
+        fn fails<R: Rng>(_rng: &mut R) -> Result<Vec<u8>, MyError> {
+            Err(MyError)
+        }
+
+        let mut rng = rand::thread_rng();
+        let result = BatchChallenge::new(&mut rng, 4, |rng: _| fails(rng));
+        assert!(matches!(result, Err(Error::Computation(MyError))));
+    }
+
+    #[test]
+    fn detection_probability_test() {
+        // Challenging every instance always catches a cheater, however few instances cheat.
+        assert_eq!(detection_probability(10, 1, 10), 1.0);
+
+        // Cheating on every instance is always caught as long as at least one is challenged.
+        assert_eq!(detection_probability(10, 10, 1), 1.0);
+
+        // Challenging nothing never catches anything.
+        assert_eq!(detection_probability(10, 3, 0), 0.0);
+
+        // Matches the textbook cut-and-choose number: cheating on 1 of 10, challenging 5,
+        // should be caught half the time.
+        assert!((detection_probability(10, 1, 5) - 0.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,220 @@
+//! Compact binary framing for shuttling commitments and revealed random factors between
+//! devices (for example over a QR code), in the spirit of SSZ/consensus-encoding: every field
+//! is length-prefixed so a reader never has to guess where one ends and the next begins, and
+//! the whole envelope carries a version byte and a trailing checksum so a truncated or
+//! malformed scan fails loudly instead of being silently handed to [`crate::check_commitment`].
+
+use crate::Error;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Wire version of the envelope format. Bump this if the layout below ever changes.
+const ENVELOPE_VERSION: u8 = 1;
+
+/// A commitment, as returned by [`crate::Challenge::commit`], wrapped so it can be framed
+/// into an [`Envelope`] instead of being passed around as a bare `Vec<u8>`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commitment(pub Vec<u8>);
+
+/// Revealed random factors, as returned by [`crate::Challenge::challenge`], wrapped for
+/// framing into an [`Envelope`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Revealed(pub Vec<u8>);
+
+impl AsRef<[u8]> for Commitment {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Revealed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// A self-describing envelope bundling a commitment, its revealed random factors, and an
+/// optional digest of the public inputs they were bound to, ready to serialize to or from a
+/// single blob (e.g. for transport over a QR code).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Envelope {
+    pub commitment: Commitment,
+    pub revealed: Revealed,
+    pub public_input_digest: Option<Vec<u8>>,
+}
+
+impl Envelope {
+    pub fn new(commitment: Commitment, revealed: Revealed, public_input_digest: Option<Vec<u8>>) -> Self {
+        Envelope {
+            commitment,
+            revealed,
+            public_input_digest,
+        }
+    }
+
+    /// Serialize to `version ‖ commitment_len ‖ commitment ‖ revealed_len ‖ revealed ‖
+    /// has_digest ‖ [digest_len ‖ digest] ‖ checksum`, with all lengths as little-endian
+    /// `u32`s and a trailing truncated checksum over everything before it.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.push(ENVELOPE_VERSION);
+        write_framed(&mut buf, &self.commitment.0);
+        write_framed(&mut buf, &self.revealed.0);
+        match &self.public_input_digest {
+            Some(digest) => {
+                buf.push(1);
+                write_framed(&mut buf, digest);
+            }
+            None => buf.push(0),
+        }
+        let checksum = checksum(&buf);
+        buf.extend_from_slice(&checksum);
+        buf
+    }
+
+    /// Parse an envelope produced by [`Envelope::to_bytes`], rejecting anything with the
+    /// wrong version, a bad checksum, or that is truncated or has trailing garbage.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 1 + 4 {
+            return Err(Error::Truncated);
+        }
+        let (body, trailing_checksum) = bytes.split_at(bytes.len() - 4);
+        if checksum(body)[..] != trailing_checksum[..] {
+            return Err(Error::ChecksumMismatch);
+        }
+
+        let mut cursor = 0;
+        let version = read_u8(body, &mut cursor)?;
+        if version != ENVELOPE_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let commitment = read_framed(body, &mut cursor)?;
+        let revealed = read_framed(body, &mut cursor)?;
+        let has_digest = read_u8(body, &mut cursor)?;
+        let public_input_digest = match has_digest {
+            0 => None,
+            1 => Some(read_framed(body, &mut cursor)?),
+            _ => return Err(Error::Truncated),
+        };
+        if cursor != body.len() {
+            // Trailing bytes after the last field we know how to parse.
+            return Err(Error::Truncated);
+        }
+
+        Ok(Envelope {
+            commitment: Commitment(commitment),
+            revealed: Revealed(revealed),
+            public_input_digest,
+        })
+    }
+}
+
+fn write_framed(buf: &mut Vec<u8>, data: &[u8]) {
+    buf.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    buf.extend_from_slice(data);
+}
+
+fn read_u8(body: &[u8], cursor: &mut usize) -> Result<u8, Error> {
+    let byte = *body.get(*cursor).ok_or(Error::Truncated)?;
+    *cursor += 1;
+    Ok(byte)
+}
+
+fn read_framed(body: &[u8], cursor: &mut usize) -> Result<Vec<u8>, Error> {
+    let len_bytes = body.get(*cursor..*cursor + 4).ok_or(Error::Truncated)?;
+    let len = u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
+    *cursor += 4;
+    let data = body.get(*cursor..*cursor + len).ok_or(Error::Truncated)?;
+    *cursor += len;
+    Ok(data.to_vec())
+}
+
+/// Truncated FNV-1a 32-bit checksum. This is only meant to catch accidental corruption or
+/// truncation in transit (e.g. a partially-scanned QR code) — it is not a cryptographic
+/// integrity check, since the commitment hash inside the envelope already provides that.
+fn checksum(bytes: &[u8]) -> [u8; 4] {
+    const FNV_OFFSET_BASIS: u32 = 0x811c_9dc5;
+    const FNV_PRIME: u32 = 0x0100_0193;
+    let mut hash = FNV_OFFSET_BASIS;
+    for &byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash.to_le_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
+
+    #[test]
+    fn roundtrip_test() {
+        let envelope = Envelope::new(
+            Commitment(vec![1, 2, 3, 4]),
+            Revealed(vec![5, 6, 7, 8, 9]),
+            Some(vec![10, 11, 12]),
+        );
+        let bytes = envelope.to_bytes();
+        let decoded = Envelope::from_bytes(&bytes).unwrap();
+        assert_eq!(envelope, decoded);
+    }
+
+    #[test]
+    fn roundtrip_without_digest_test() {
+        let envelope = Envelope::new(Commitment(vec![1]), Revealed(vec![2]), None);
+        let bytes = envelope.to_bytes();
+        let decoded = Envelope::from_bytes(&bytes).unwrap();
+        assert_eq!(envelope, decoded);
+    }
+
+    #[test]
+    fn rejects_truncated_buffer() {
+        let envelope = Envelope::new(Commitment(vec![1, 2, 3]), Revealed(vec![4, 5]), None);
+        let mut bytes = envelope.to_bytes();
+        bytes.truncate(bytes.len() - 3);
+        // A truncated body changes the checksum too, so either error is an acceptable
+        // rejection - what matters is that a partial scan is never accepted.
+        assert!(Envelope::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_over_long_buffer() {
+        let envelope = Envelope::new(Commitment(vec![1, 2, 3]), Revealed(vec![4, 5]), None);
+        let mut bytes = envelope.to_bytes();
+        bytes.push(0xff);
+        assert!(Envelope::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_corrupted_checksum() {
+        let envelope = Envelope::new(Commitment(vec![1, 2, 3]), Revealed(vec![4, 5]), None);
+        let mut bytes = envelope.to_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        assert!(matches!(
+            Envelope::from_bytes(&bytes),
+            Err(Error::ChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn rejects_wrong_version() {
+        let envelope = Envelope::new(Commitment(vec![1, 2, 3]), Revealed(vec![4, 5]), None);
+        let mut bytes = envelope.to_bytes();
+        bytes[0] = ENVELOPE_VERSION + 1;
+        // Recompute the checksum so this test isolates the version check.
+        let body_len = bytes.len() - 4;
+        let fixed_checksum = checksum(&bytes[..body_len]);
+        bytes[body_len..].copy_from_slice(&fixed_checksum);
+        assert!(matches!(
+            Envelope::from_bytes(&bytes),
+            Err(Error::UnsupportedVersion(v)) if v == ENVELOPE_VERSION + 1
+        ));
+    }
+}
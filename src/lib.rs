@@ -3,17 +3,21 @@
 //! ## Example
 //!
 //! ```
+//! # // This example relies on the default (non-`no_std`) `Challenge`, whose buffer grows
+//! # // with a plain `Vec` instead of carrying a fixed-capacity const generic.
+//! # #[cfg(feature = "std")]
+//! # {
 //! use benaloh_challenge;
 //! use rand::{Rng, CryptoRng};
 //! use sha2::{Sha256, Digest};
 //! use rsa::padding::PaddingScheme;
-//! use rsa::{PublicKey, RsaPrivateKey, RsaPublicKey};
+//! use rsa::{PublicKey, PublicKeyParts, RsaPrivateKey, RsaPublicKey};
 //!
 //! // Untrusted computation that is deterministic with the exception of an RNG
-//! // For this example we encrypt a vote for an election using RSA.
-//! fn untrusted_computation<R: Rng + CryptoRng>(rng: &mut R, key: &RsaPublicKey, message: &[u8]) -> Vec<u8> {
-//!     let ciphertext = key.encrypt(rng, PaddingScheme::PKCS1v15Encrypt, message).unwrap();
-//!     return ciphertext;
+//! // For this example we encrypt a vote for an election using RSA. Returning a `Result`
+//! // lets a real encryption failure propagate instead of panicking mid-protocol.
+//! fn untrusted_computation<R: Rng + CryptoRng>(rng: &mut R, key: &RsaPublicKey, message: &[u8]) -> Result<Vec<u8>, rsa::errors::Error> {
+//!     key.encrypt(rng, PaddingScheme::PKCS1v15Encrypt, message)
 //! };
 //!
 //! let mut rng = rand::thread_rng();
@@ -25,14 +29,19 @@
 //!     untrusted_computation(rng, &public_key, vote)
 //! });
 //!
+//! // Public context that the commitment should be bound to, so a commitment produced for
+//! // this public key and vote can't be replayed against a different one.
+//! let public_key_bytes = public_key.n().to_bytes_be();
+//! let public_inputs: [(&str, &[u8]); 1] = [("public_key", &public_key_bytes)];
+//!
 //! // Get the commitment
-//! let commitment = challenge.commit(&mut hasher);
+//! let commitment = challenge.commit(&mut hasher, &public_inputs).unwrap();
 //!
 //! // Reveal the secret random factors used in the encryption. This also invalidates the results.
 //! let revealed = challenge.challenge();
 //!
 //! // Check the commitment on a different (trusted) device.
-//! let result = benaloh_challenge::check_commitment(&mut hasher, &commitment, &revealed, |rng: _| {
+//! let result = benaloh_challenge::check_commitment(&mut hasher, &commitment, &revealed, &public_inputs, |rng: _| {
 //!     untrusted_computation(rng, &public_key, vote)
 //! });
 //! if result.is_err() {
@@ -42,11 +51,12 @@
 //! // In a real voting application, the user would be given the choice to change their vote here.
 //!
 //! // Get another commitment
-//! challenge.commit(&mut hasher);
+//! challenge.commit(&mut hasher, &public_inputs).unwrap();
 //!
 //! // We could challenge here again if we wanted
 //! // but instead we get the results, discarding the random factors.
 //! let ciphertext = challenge.into_results();
+//! # }
 //!
 //! ```
 //!
@@ -71,7 +81,23 @@
 //!
 //! In the context of an election, the Benaloh Challange ensues that systematic cheating by voting machines will be discoverd with a very high probability. Changing a few votes has a decent chance of going undetected, but every time the voting machine cheats, it risks being caught if misjudges when a user might choose to challenge.=
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// `no_std` builds still need a heap for `Vec`-shaped results and commitments (everything here
+// is inherently variable-length) - `RecordingRng`'s fixed-capacity buffer is the one exception.
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+// `cargo test` always links `std` for its own test harness, regardless of this crate's
+// `no_std`-ness; re-admit it explicitly so `#[cfg(test)]` code below can keep using it.
+#[cfg(all(not(feature = "std"), test))]
+extern crate std;
+
 use digest::{Digest, FixedOutputReset};
+#[cfg(feature = "std")]
 use thiserror::Error;
 use rand::{RngCore, CryptoRng};
 use zeroize::Zeroize;
@@ -79,18 +105,81 @@ use zeroize::Zeroize;
 mod rng;
 pub use rng::PlaybackRng;
 pub use rng::RecordingRng;
+#[cfg(feature = "async")]
+pub use rng::AsyncEntropySource;
+#[cfg(feature = "async")]
+pub use rng::AsyncRecordingRng;
+
+mod transcript;
+use transcript::Transcript;
+
+pub mod batch;
+pub mod envelope;
+pub mod testing;
 
 /// Error types
+///
+/// `E` is the error type of the untrusted computation passed to [`Challenge`] and
+/// [`check_commitment`]; it defaults to [`core::convert::Infallible`] so call sites that don't
+/// use a fallible computation (or don't otherwise touch [`Error::Computation`]) can keep
+/// writing `Error` unparameterized.
+#[cfg(feature = "std")]
 #[derive(Error, Debug)]
-pub enum Error {
+pub enum Error<E = core::convert::Infallible> {
     #[error("benaloh_challenge: failed verification - commitments do not match")]
     VerificationFailed,
+    #[error("benaloh_challenge: envelope has unsupported version {0}")]
+    UnsupportedVersion(u8),
+    #[error("benaloh_challenge: envelope checksum does not match - payload is corrupted")]
+    ChecksumMismatch,
+    #[error("benaloh_challenge: envelope is truncated or malformed")]
+    Truncated,
+    #[error("benaloh_challenge: untrusted computation failed: {0}")]
+    Computation(E),
 }
 
+/// `no_std` counterpart of [`Error`]: identical variants and messages, but `thiserror`'s derive
+/// always implements `std::error::Error` (unconditionally, regardless of the deriving crate's
+/// own `no_std`-ness), so it can't be used here - [`core::fmt::Display`] and
+/// [`core::error::Error`] are implemented by hand below instead.
+#[cfg(not(feature = "std"))]
+#[derive(Debug)]
+pub enum Error<E = core::convert::Infallible> {
+    VerificationFailed,
+    UnsupportedVersion(u8),
+    ChecksumMismatch,
+    Truncated,
+    Computation(E),
+}
+
+#[cfg(not(feature = "std"))]
+impl<E: core::fmt::Display> core::fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::VerificationFailed => {
+                write!(f, "benaloh_challenge: failed verification - commitments do not match")
+            }
+            Error::UnsupportedVersion(version) => {
+                write!(f, "benaloh_challenge: envelope has unsupported version {version}")
+            }
+            Error::ChecksumMismatch => write!(
+                f,
+                "benaloh_challenge: envelope checksum does not match - payload is corrupted"
+            ),
+            Error::Truncated => write!(f, "benaloh_challenge: envelope is truncated or malformed"),
+            Error::Computation(e) => write!(f, "benaloh_challenge: untrusted computation failed: {e}"),
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<E: core::fmt::Debug + core::fmt::Display> core::error::Error for Error<E> {}
+
 /// A benaloh challenge that wraps untrusted computation in a way that can be challanged.
-pub struct Challenge<'a, R: RngCore + CryptoRng, C>
+#[cfg(feature = "std")]
+pub struct Challenge<'a, R: RngCore + CryptoRng, C, E>
 where
-    C: Fn(&mut RecordingRng<'a, R>) -> Vec<u8>, // TODO: Return a result.
+    C: Fn(&mut RecordingRng<'a, R>) -> Result<Vec<u8>, E>,
 {
     rng: RecordingRng<'a, R>,
     computation: C,
@@ -99,9 +188,28 @@ where
     committed: bool,
 }
 
-impl<'a, R: RngCore + CryptoRng, C> Challenge<'a, R, C>
+/// A benaloh challenge that wraps untrusted computation in a way that can be challanged.
+///
+/// This is the `no_std` counterpart of the default `Challenge`: it records through a
+/// [`RecordingRng`] backed by a fixed-capacity `N`-byte buffer instead of a heap-growing
+/// `Vec<u8>`, so `N` has to be threaded through here too rather than hardcoding the
+/// non-`no_std` `RecordingRng<'a, R>` shape.
+#[cfg(not(feature = "std"))]
+pub struct Challenge<'a, R: RngCore + CryptoRng, C, E, const N: usize>
+where
+    C: Fn(&mut RecordingRng<'a, R, N>) -> Result<Vec<u8>, E>,
+{
+    rng: RecordingRng<'a, R, N>,
+    computation: C,
+    result: Vec<u8>,
+    cached_random: Vec<u8>,
+    committed: bool,
+}
+
+#[cfg(feature = "std")]
+impl<'a, R: RngCore + CryptoRng, C, E> Challenge<'a, R, C, E>
 where
-    C: Fn(&mut RecordingRng<'a, R>) -> Vec<u8>,
+    C: Fn(&mut RecordingRng<'a, R>) -> Result<Vec<u8>, E>,
 {
     /// Create a new benaloh challenge with the given RNG and untrusted computation.
     ///
@@ -110,7 +218,7 @@ where
     /// ## Example:
     ///
     /// ```ignore
-    ///fn untrusted_computation<R: Rng>(rng: &mut R, some_data: foo, other_data: bar) -> Vec<u8> {
+    ///fn untrusted_computation<R: Rng>(rng: &mut R, some_data: foo, other_data: bar) -> Result<Vec<u8>, MyError> {
     ///  // Some unstrusted computation that uses an RNG and other data.
     ///  // The results of this computation must be a vector of bytes.
     ///};
@@ -140,15 +248,129 @@ where
 
     /// Commit the results and get the commitment
     ///
+    /// `public_inputs` are labeled, caller-supplied context (for example the public key and
+    /// candidate list the untrusted computation ran against). They are absorbed into the
+    /// commitment's transcript ahead of the result, so a commitment only checks out on the
+    /// verifying side if the public context matches exactly. This binds the commitment to
+    /// *which* computation was run, not just its output, and prevents a commitment produced
+    /// for one context from being replayed against another.
+    ///
     /// This method generates both the results and the commitment, so must be called before `into_results()` is called.
-    pub fn commit<H: Digest + FixedOutputReset>(&mut self, hasher: &mut H) -> Vec<u8> {
-        self.result = (self.computation)(&mut self.rng);
-        self.cached_random = self.rng.fetch_recorded();
-        Digest::update(hasher, &self.result);
-        let commitment = hasher.finalize_fixed_reset().to_vec();
+    ///
+    /// If the untrusted computation returns `Err`, this returns `Error::Computation` and
+    /// zeroizes any cached random factors and partial result from this (or a prior) round
+    /// before returning, so a failing computation can't leak secret material.
+    pub fn commit<H: Digest + FixedOutputReset>(
+        &mut self,
+        hasher: &mut H,
+        public_inputs: &[(&str, &[u8])],
+    ) -> Result<Vec<u8>, Error<E>> {
+        let computed = (self.computation)(&mut self.rng);
+        let mut partial_random = self.rng.fetch_recorded();
+
+        let result = match computed {
+            Ok(result) => result,
+            Err(e) => {
+                self.result.zeroize();
+                self.cached_random.zeroize();
+                partial_random.zeroize();
+                return Err(Error::Computation(e));
+            }
+        };
+
+        self.result = result;
+        self.cached_random = partial_random;
+
+        let mut transcript = Transcript::new(hasher);
+        transcript.append_public_inputs(public_inputs);
+        transcript.append("result", &self.result);
+        let commitment = transcript.finalize();
+        self.committed = true;
+
+        Ok(commitment)
+    }
+
+    /// Challange the results, revealing the random factors and invalidating the results of the computaton.
+    ///
+    /// The revealing random factors must be given to the challenging device so it may validate the commitment.
+    pub fn challenge(&mut self) -> Vec<u8> {
+        if !self.committed {
+            panic!("benaloh_challenge: Challenge.commit() must be invoked before calling Challenge.challlenge()")
+        }
+        self.result.zeroize();
+        let mut cached_random = Vec::new();
+        core::mem::swap(&mut cached_random, &mut self.cached_random);
+        self.committed = false;
+        cached_random
+    }
+
+    /// Get the results of the untrusted computation, discarding (zeroing) the secret random factors.
+    ///
+    /// This method will panic if called before `commit()` is called (since `commit()` generates the results).
+    pub fn into_results(mut self) -> Vec<u8> {
+        if !self.committed {
+            panic!("benaloh_challenge: Challenge.commit() must be invoked before calling Challenge.into_results()")
+        }
+        self.cached_random.zeroize();
+        self.result
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<'a, R: RngCore + CryptoRng, C, E, const N: usize> Challenge<'a, R, C, E, N>
+where
+    C: Fn(&mut RecordingRng<'a, R, N>) -> Result<Vec<u8>, E>,
+{
+    /// Create a new benaloh challenge with the given RNG and untrusted computation.
+    ///
+    /// While this method takes a closure, it is generally recommended to create a separate `untrusted_computation` function and wrap it in the closure.
+    ///
+    /// `N` is the capacity of the underlying `no_std` [`RecordingRng`]'s recording buffer; it
+    /// is usually inferred from the closure's argument type.
+    pub fn new(rng: &'a mut R, untrusted_computation: C) -> Self {
+        let recording_rng = RecordingRng::new(rng);
+        Challenge {
+            rng: recording_rng,
+            computation: untrusted_computation,
+            result: Vec::<u8>::new(),
+            cached_random: Vec::<u8>::new(),
+            committed: false,
+        }
+    }
+
+    /// Commit the results and get the commitment
+    ///
+    /// See the non-`no_std` [`Challenge::commit`] for the full behavior; this differs only in
+    /// draining the fixed-capacity recording buffer into a heap-allocated `Vec<u8>` before
+    /// zeroizing and transcripting it, since `Challenge` itself always needs `alloc`.
+    pub fn commit<H: Digest + FixedOutputReset>(
+        &mut self,
+        hasher: &mut H,
+        public_inputs: &[(&str, &[u8])],
+    ) -> Result<Vec<u8>, Error<E>> {
+        let computed = (self.computation)(&mut self.rng);
+        let mut partial_random: Vec<u8> = self.rng.fetch_recorded().iter().copied().collect();
+
+        let result = match computed {
+            Ok(result) => result,
+            Err(e) => {
+                self.result.zeroize();
+                self.cached_random.zeroize();
+                partial_random.zeroize();
+                return Err(Error::Computation(e));
+            }
+        };
+
+        self.result = result;
+        self.cached_random = partial_random;
+
+        let mut transcript = Transcript::new(hasher);
+        transcript.append_public_inputs(public_inputs);
+        transcript.append("result", &self.result);
+        let commitment = transcript.finalize();
         self.committed = true;
 
-        commitment
+        Ok(commitment)
     }
 
     /// Challange the results, revealing the random factors and invalidating the results of the computaton.
@@ -160,7 +382,7 @@ where
         }
         self.result.zeroize();
         let mut cached_random = Vec::new();
-        std::mem::swap(&mut cached_random, &mut self.cached_random);
+        core::mem::swap(&mut cached_random, &mut self.cached_random);
         self.committed = false;
         cached_random
     }
@@ -180,38 +402,47 @@ where
 /// Check the commitment given by a challenge.
 /// This should be done on a different device seperately from the device being challenged.
 ///
+/// `public_inputs` must be the exact same labeled context passed to `Challenge::commit`;
+/// if it differs (wrong public key, different candidate list, etc.) the commitment will not
+/// match even if the recomputed result happens to.
+///
 /// This function will return an error if verification of the challenge failed (meaning the challenged device attempted to cheat).
-pub fn check_commitment<H: Digest + FixedOutputReset, C>(
+pub fn check_commitment<H: Digest + FixedOutputReset, C, E>(
     hasher: &mut H,
     commitment: &[u8],
     revealed_random: &[u8],
+    public_inputs: &[(&str, &[u8])],
     untrusted_computation: C,
-) -> Result<(), Error>
+) -> Result<(), Error<E>>
 where
-    C: Fn(&mut PlaybackRng) -> Vec<u8>,
+    C: Fn(&mut PlaybackRng) -> Result<Vec<u8>, E>,
 {
     let mut playback = PlaybackRng::new(revealed_random);
-    let result = (untrusted_computation)(&mut playback);
-    Digest::update( hasher, result);
-    if hasher.finalize_fixed_reset().to_vec() != commitment.to_vec() {
+    let result = (untrusted_computation)(&mut playback).map_err(Error::Computation)?;
+
+    let mut transcript = Transcript::new(hasher);
+    transcript.append_public_inputs(public_inputs);
+    transcript.append("result", &result);
+    if transcript.finalize() != commitment.to_vec() {
         return Err(Error::VerificationFailed);
     }
     Ok(())
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use crate::check_commitment;
     use crate::{Challenge, Error};
     use rand::{self, Rng, CryptoRng, RngCore};
     use sha2::{Digest, Sha256};
+    use std::convert::Infallible;
 
     #[test]
     fn copy_rng_test() -> Result<(), Error> {
-        fn untrusted_computation<R: Rng>(rng: &mut R, _foo: i32) -> Vec<u8> {
+        fn untrusted_computation<R: Rng>(rng: &mut R, _foo: i32) -> Result<Vec<u8>, Infallible> {
             let mut bytes = vec![0; 8];
             rng.fill_bytes(&mut bytes);
-            return bytes.to_vec();
+            Ok(bytes.to_vec())
         }
 
         let mut rng = rand::thread_rng();
@@ -219,15 +450,15 @@ mod tests {
         let some_foo = 123;
 
         let mut challenge = Challenge::new(&mut rng, |rng: _| untrusted_computation(rng, some_foo));
-        let commitment = challenge.commit(&mut hasher);
+        let commitment = challenge.commit(&mut hasher, &[])?;
         let revealed = challenge.challenge();
 
         // Check the challenge on a different (trusted) device.
-        check_commitment(&mut hasher, &commitment, &revealed, |rng: _| {
+        check_commitment(&mut hasher, &commitment, &revealed, &[], |rng: _| {
             untrusted_computation(rng, some_foo)
         })?;
 
-        challenge.commit(&mut hasher);
+        challenge.commit(&mut hasher, &[])?;
 
         let _results = challenge.into_results();
 
@@ -235,7 +466,7 @@ mod tests {
     }
 
     #[test]
-    fn rsa_test() -> Result<(), Error> {
+    fn rsa_test() -> Result<(), Error<rsa::errors::Error>> {
         use rsa::padding::PaddingScheme;
         use rsa::{PublicKey, RsaPrivateKey};
 
@@ -243,14 +474,8 @@ mod tests {
             rng: &mut R,
             public_key: &K,
             message: &[u8],
-        ) -> Vec<u8> {
-            // TODO: return Result<(), Error>
-
-            let ciphertext = public_key
-                .encrypt(rng, PaddingScheme::PKCS1v15Encrypt, message)
-                .unwrap();
-
-            ciphertext
+        ) -> Result<Vec<u8>, rsa::errors::Error> {
+            public_key.encrypt(rng, PaddingScheme::PKCS1v15Encrypt, message)
         }
 
         let mut rng = rand::thread_rng();
@@ -263,19 +488,21 @@ mod tests {
             untrusted_computation(rng, &public_key, message)
         });
 
+        let public_inputs: [(&str, &[u8]); 1] = [("message", message)];
+
         // Get the commitment
-        let commitment = challenge.commit(&mut hasher);
+        let commitment = challenge.commit(&mut hasher, &public_inputs)?;
 
         // Reveal the secret random factors used in the encryption
         let revealed = challenge.challenge();
 
         // Check the challenge on a different (trusted) device.
-        check_commitment(&mut hasher, &commitment, &revealed, |rng: _| {
+        check_commitment(&mut hasher, &commitment, &revealed, &public_inputs, |rng: _| {
             untrusted_computation(rng, &public_key, message)
         })?;
 
         // Get the real results, discarding the random factors.
-        challenge.commit(&mut hasher);
+        challenge.commit(&mut hasher, &public_inputs)?;
         let _ciphertext = challenge.into_results();
 
         Ok(())
@@ -284,29 +511,119 @@ mod tests {
     #[test]
     fn cheat_test() -> Result<(), Error> {
         use crate::PlaybackRng;
-        fn untrusted_computation<R: Rng>(rng: &mut R) -> Vec<u8> {
+        fn untrusted_computation<R: Rng>(rng: &mut R) -> Result<Vec<u8>, Infallible> {
             let mut bytes = vec![0; 8];
             rng.fill_bytes(&mut bytes);
-            return bytes.to_vec();
+            Ok(bytes.to_vec())
         }
 
         let incrementing = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
         let mut rng = PlaybackRng::new(&incrementing);
         let mut hasher = Sha256::new();
 
-        let mut challenge = Challenge::new(&mut rng, |rng: _| untrusted_computation(rng));
-        let commitment = challenge.commit(&mut hasher);
+        let mut challenge = Challenge::new(&mut rng, untrusted_computation);
+        let commitment = challenge.commit(&mut hasher, &[])?;
         let _revealed = challenge.challenge();
 
         // Cheat!  Replace revealed with out cheat values.
         let revealed = vec![0, 0, 0, 0, 0, 0, 0, 0, 0];
 
         // Check the challenge on a different (trusted) device.
-        let ok = check_commitment(&mut hasher, &commitment, &revealed, |rng: _| {
+        let ok = check_commitment(&mut hasher, &commitment, &revealed, &[], |rng: _| {
             untrusted_computation(rng)
         });
 
         assert!(ok.is_err());
         Ok(())
     }
+
+    #[test]
+    fn public_input_binding_test() -> Result<(), Error> {
+        fn untrusted_computation<R: Rng>(rng: &mut R) -> Result<Vec<u8>, Infallible> {
+            let mut bytes = vec![0; 8];
+            rng.fill_bytes(&mut bytes);
+            Ok(bytes.to_vec())
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut hasher = Sha256::new();
+
+        let mut challenge = Challenge::new(&mut rng, untrusted_computation);
+        let commitment = challenge.commit(&mut hasher, &[("candidate", b"Alice")])?;
+        let revealed = challenge.challenge();
+
+        // The verifier checks against a different public input than what was committed to,
+        // e.g. because the candidate list shown to the voter was swapped out. This must fail
+        // even though the result and random factors are unchanged.
+        let ok = check_commitment(&mut hasher, &commitment, &revealed, &[("candidate", b"Bob")], |rng: _| {
+            untrusted_computation(rng)
+        });
+        assert!(ok.is_err());
+
+        // The matching public input still checks out.
+        check_commitment(&mut hasher, &commitment, &revealed, &[("candidate", b"Alice")], |rng: _| {
+            untrusted_computation(rng)
+        })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn fallible_computation_test() {
+        #[derive(Debug, thiserror::Error)]
+        #[error("encryption failed")]
+        struct MyError;
+
+        fn untrusted_computation<R: Rng>(rng: &mut R, fail: bool) -> Result<Vec<u8>, MyError> {
+            let mut bytes = vec![0; 8];
+            rng.fill_bytes(&mut bytes);
+            if fail {
+                return Err(MyError);
+            }
+            Ok(bytes)
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut hasher = Sha256::new();
+
+        let mut challenge = Challenge::new(&mut rng, |rng: _| untrusted_computation(rng, true));
+        let result = challenge.commit(&mut hasher, &[]);
+        assert!(matches!(result, Err(Error::Computation(MyError))));
+    }
+}
+
+/// `no_std` counterpart of the tests above: `Challenge`'s `no_std` form carries an extra
+/// const generic (the recording buffer's capacity `N`), so the closures here need an explicit
+/// `RecordingRng<_, 8>` annotation to pin it, whereas the non-`no_std` tests above let it infer.
+#[cfg(all(test, not(feature = "std")))]
+mod no_std_tests {
+    use crate::{check_commitment, Challenge, Error, RecordingRng};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use rand::{self, Rng};
+    use sha2::{Digest, Sha256};
+    use std::convert::Infallible;
+
+    #[test]
+    fn copy_rng_test() -> Result<(), Error> {
+        fn untrusted_computation<R: Rng>(rng: &mut R) -> Result<Vec<u8>, Infallible> {
+            let mut bytes = vec![0; 8];
+            rng.fill_bytes(&mut bytes);
+            Ok(bytes)
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut hasher = Sha256::new();
+
+        let mut challenge =
+            Challenge::new(&mut rng, |rng: &mut RecordingRng<_, 8>| untrusted_computation(rng));
+        let commitment = challenge.commit(&mut hasher, &[])?;
+        let revealed = challenge.challenge();
+
+        check_commitment(&mut hasher, &commitment, &revealed, &[], |rng: _| {
+            untrusted_computation(rng)
+        })?;
+
+        Ok(())
+    }
 }
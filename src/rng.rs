@@ -1,14 +1,22 @@
 use rand::{Rng, CryptoRng};
 use rand_core::{impls, Error, RngCore};
+#[cfg(any(feature = "std", feature = "async"))]
 use zeroize::Zeroize;
 
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
 /// This recording RNG wraps a real RNG and records the random data as it is passed to the caller
 /// When it is dropped, the recording RNG calls `zeroize()` to zero the recorded data in memory.
+#[cfg(feature = "std")]
 pub struct RecordingRng<'a, R: Rng> {
     inner: &'a mut R,
     recorded: Vec<u8>,
 }
 
+#[cfg(feature = "std")]
 impl<'a, R: Rng> RecordingRng<'a, R> {
     /// Create a new recording RNG from a real RNG
     pub fn new(rng: &'a mut R) -> Self {
@@ -34,6 +42,7 @@ impl<'a, R: Rng> RecordingRng<'a, R> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a, R: Rng> RngCore for RecordingRng<'a, R> {
     fn next_u32(&mut self) -> u32 {
         impls::next_u32_via_fill(self)
@@ -54,8 +63,185 @@ impl<'a, R: Rng> RngCore for RecordingRng<'a, R> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a, R: RngCore + CryptoRng> CryptoRng for RecordingRng<'a, R> {}
 
+/// A `no_std` recording RNG backed by a bounded buffer (a `heapless::Vec`) instead of an
+/// unboundedly-growing `std::Vec`, for embedded hardware — such as a voting machine's secure
+/// element — that has no heap to grow into. The buffer is created by [`RecordingRng::new`]
+/// itself; the caller chooses only its capacity `N`, fixed at compile time, and cannot pass in
+/// a buffer of its own; `fill_bytes` panics and `try_fill_bytes` returns an error if the caller asks for more
+/// randomness than fits, rather than silently growing or truncating.
+#[cfg(not(feature = "std"))]
+pub struct RecordingRng<'a, R: Rng, const N: usize> {
+    inner: &'a mut R,
+    recorded: heapless::Vec<u8, N>,
+}
+
+#[cfg(not(feature = "std"))]
+impl<'a, R: Rng, const N: usize> RecordingRng<'a, R, N> {
+    /// Create a new recording RNG from a real RNG, recording into a fixed-capacity buffer.
+    pub fn new(rng: &'a mut R) -> Self {
+        RecordingRng {
+            inner: rng,
+            recorded: heapless::Vec::new(),
+        }
+    }
+
+    /// Fetch the recorded bytes, leaving the internal buffer empty.
+    pub fn fetch_recorded(&mut self) -> heapless::Vec<u8, N> {
+        let mut recorded = heapless::Vec::new();
+        core::mem::swap(&mut recorded, &mut self.recorded);
+        recorded
+    }
+
+    /// Transform the recording RNG into a [`PlaybackRng`] for playback in `check_commitment`.
+    /// This requires `alloc`, since verification is expected to happen on a host with a heap
+    /// even when the recording itself happened on constrained hardware.
+    pub fn into_playback(self) -> PlaybackRng {
+        PlaybackRng {
+            recorded: self.recorded.iter().copied().collect(),
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<'a, R: Rng, const N: usize> RngCore for RecordingRng<'a, R, N> {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest)
+            .expect("benaloh_challenge: no_std recording buffer capacity exceeded")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.inner.fill_bytes(dest);
+        self.recorded
+            .extend_from_slice(dest)
+            .map_err(|_| capacity_exceeded_error())
+    }
+}
+
+/// `rand_core::Error::new` takes any `std::error::Error` and is only available with `rand`'s
+/// `std` feature, which a genuine `no_std` build can't rely on - so build the error from a raw
+/// code instead, per `rand_core::Error::CUSTOM_START`'s documented convention for caller-defined
+/// codes.
+#[cfg(not(feature = "std"))]
+fn capacity_exceeded_error() -> Error {
+    const CAPACITY_EXCEEDED: u32 = Error::CUSTOM_START;
+    Error::from(core::num::NonZeroU32::new(CAPACITY_EXCEEDED).unwrap())
+}
+
+#[cfg(not(feature = "std"))]
+impl<'a, R: RngCore + CryptoRng, const N: usize> CryptoRng for RecordingRng<'a, R, N> {}
+
+/// A non-blocking entropy source, e.g. a peripheral driver that delivers random bytes
+/// asynchronously (such as the nRF RNG peripheral driver), instead of the synchronous
+/// `rand::RngCore` the rest of this crate relies on.
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)] // single-threaded embedded targets don't need a `Send` bound here
+pub trait AsyncEntropySource {
+    /// Fill `dest` with random bytes, awaiting the entropy source as needed.
+    async fn fill_bytes(&mut self, dest: &mut [u8]);
+}
+
+/// Like [`RecordingRng`], but wraps an [`AsyncEntropySource`] instead of a synchronous
+/// `rand::RngCore`, for hardware whose RNG peripheral is only reachable asynchronously.
+#[cfg(feature = "async")]
+pub struct AsyncRecordingRng<'a, S: AsyncEntropySource> {
+    inner: &'a mut S,
+    recorded: Vec<u8>,
+}
+
+#[cfg(feature = "async")]
+impl<'a, S: AsyncEntropySource> AsyncRecordingRng<'a, S> {
+    /// Create a new async recording RNG from a non-blocking entropy source.
+    pub fn new(source: &'a mut S) -> Self {
+        AsyncRecordingRng {
+            inner: source,
+            recorded: Vec::new(),
+        }
+    }
+
+    /// Fill `dest` with random bytes from the wrapped entropy source, awaiting delivery and
+    /// recording every byte as it arrives.
+    pub async fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.inner.fill_bytes(dest).await;
+        self.recorded.extend_from_slice(dest);
+    }
+
+    /// Fetch the recorded bytes, leaving the internal buffer empty and zeroized.
+    pub fn fetch_recorded(&mut self) -> Vec<u8> {
+        let recorded = self.recorded.drain(..).collect();
+        self.recorded.zeroize();
+        recorded
+    }
+
+    /// Transform the recording RNG into a [`PlaybackRng`] for playback in `check_commitment`.
+    /// This consumes the recording RNG so it may no longer be used.
+    pub fn into_playback(self) -> PlaybackRng {
+        PlaybackRng {
+            recorded: self.recorded,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod async_tests {
+    use super::{AsyncEntropySource, AsyncRecordingRng};
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use rand_core::RngCore;
+
+    /// A fake entropy source whose `fill_bytes` future resolves immediately, so it can be
+    /// driven with a trivial busy-poll executor instead of pulling in a real one.
+    struct CountingSource {
+        next: u8,
+    }
+
+    impl AsyncEntropySource for CountingSource {
+        async fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest.iter_mut() {
+                self.next = self.next.wrapping_add(1);
+                *byte = self.next;
+            }
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let waker = Waker::noop();
+        let mut cx = Context::from_waker(waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn async_recording_rng_records_and_replays() {
+        let mut source = CountingSource { next: 0 };
+        let mut recorder = AsyncRecordingRng::new(&mut source);
+
+        let mut buffer = [0u8; 4];
+        block_on(recorder.fill_bytes(&mut buffer));
+        assert_eq!(buffer, [1, 2, 3, 4]);
+
+        let mut playback = recorder.into_playback();
+        let mut replayed = [0u8; 4];
+        playback.fill_bytes(&mut replayed);
+        assert_eq!(replayed, buffer);
+    }
+}
+
 /// A static vector of bytes that masquerades as an RNG.
 /// This is used to check the commitment of a challange, and shouldn't be used anywhere else.
 pub struct PlaybackRng {
@@ -91,9 +277,7 @@ impl RngCore for PlaybackRng {
 
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         if self.recorded.len() < dest.len() {
-            Err(Error::new(
-                "benaloh_challenge: commitment-check read more RNG values than commitment",
-            ))
+            Err(exhausted_playback_error())
         } else {
             self.fill_bytes(dest);
             Ok(())
@@ -101,6 +285,22 @@ impl RngCore for PlaybackRng {
     }
 }
 
+/// With `std`, the error carries a message via `rand_core::Error::new`. That constructor takes
+/// any `std::error::Error` and is only available with `rand`'s `std` feature, which a `no_std`
+/// build can't rely on - so the `no_std` version below builds the error from a raw code instead
+/// (still via `rand_core::Error`, so callers match on the same type either way), per
+/// `rand_core::Error::CUSTOM_START`'s documented convention.
+#[cfg(feature = "std")]
+fn exhausted_playback_error() -> Error {
+    Error::new("benaloh_challenge: commitment-check read more RNG values than commitment")
+}
+
+#[cfg(not(feature = "std"))]
+fn exhausted_playback_error() -> Error {
+    const EXHAUSTED_PLAYBACK: u32 = Error::CUSTOM_START + 1;
+    Error::from(core::num::NonZeroU32::new(EXHAUSTED_PLAYBACK).unwrap())
+}
+
 impl CryptoRng for PlaybackRng {}
 
 mod test {
@@ -135,7 +335,10 @@ mod test {
         use crate::rng::RecordingRng;
 
         let mut rng = CountingRng { count: 0 };
+        #[cfg(feature = "std")]
         let mut recorder = RecordingRng::new(&mut rng);
+        #[cfg(not(feature = "std"))]
+        let mut recorder: RecordingRng<_, 32> = RecordingRng::new(&mut rng);
 
         assert_eq!(recorder.next_u64(), 1);
         assert_eq!(recorder.next_u64(), 2);
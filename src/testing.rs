@@ -0,0 +1,202 @@
+//! Reusable test harness for downstream users to gain confidence that their particular
+//! `untrusted_computation` is actually sensitive to the RNG input the Benaloh challenge
+//! relies on. A computation that ignores its RNG will trivially pass any challenge -
+//! `check_commitment` only ever sees what the computation chooses to do with the randomness
+//! it's handed, so it has no way to tell a computation that used it honestly from one that
+//! didn't. This module can't fix that on its own, but it can catch it during development.
+
+use crate::{check_commitment, Challenge, Error, PlaybackRng, RecordingRng};
+use digest::{Digest, FixedOutputReset};
+use rand::{CryptoRng, RngCore};
+use core::fmt::Debug;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Object-safe stand-in for `RngCore + CryptoRng`, so a single `computation` closure can be
+/// run once against a [`RecordingRng`] and again against a [`PlaybackRng`] without being
+/// generic over which concrete RNG type it receives.
+pub trait CryptoRngCore: RngCore + CryptoRng {}
+impl<T: RngCore + CryptoRng> CryptoRngCore for T {}
+
+/// Commit honestly with `computation`, then systematically (a) flip individual bits of the
+/// revealed random factors and (b) perturb the recomputed result, asserting that
+/// `check_commitment` rejects every mutated case while accepting the untouched transcript.
+///
+/// # Panics
+///
+/// Panics (with a message naming the specific mutation) if any tampered transcript is
+/// accepted, if the honest, untampered transcript is rejected, or if `computation` never
+/// drew any randomness at all - the purest form of "ignores its RNG", which would otherwise
+/// leave part (a) below a no-op and let the bug this harness exists to catch slip through.
+#[cfg(feature = "std")]
+pub fn assert_detects_tampering<R, H, E>(
+    rng: &mut R,
+    computation: impl Fn(&mut dyn CryptoRngCore) -> Result<Vec<u8>, E>,
+    hasher: &mut H,
+) where
+    R: RngCore + CryptoRng,
+    H: Digest + FixedOutputReset,
+    E: Debug,
+{
+    let mut challenge = Challenge::new(rng, |rng: &mut RecordingRng<R>| computation(rng));
+    let commitment = challenge
+        .commit(hasher, &[])
+        .expect("honest computation must not fail");
+    let revealed = challenge.challenge();
+    assert_tampering_detected(hasher, &commitment, &revealed, computation);
+}
+
+/// `no_std` counterpart of [`assert_detects_tampering`]: same behavior, but committing
+/// through the fixed-capacity `N`-byte [`RecordingRng`] instead of the heap-growing one.
+#[cfg(not(feature = "std"))]
+pub fn assert_detects_tampering<R, H, E, const N: usize>(
+    rng: &mut R,
+    computation: impl Fn(&mut dyn CryptoRngCore) -> Result<Vec<u8>, E>,
+    hasher: &mut H,
+) where
+    R: RngCore + CryptoRng,
+    H: Digest + FixedOutputReset,
+    E: Debug,
+{
+    let mut challenge = Challenge::new(rng, |rng: &mut RecordingRng<R, N>| computation(rng));
+    let commitment = challenge
+        .commit(hasher, &[])
+        .expect("honest computation must not fail");
+    let revealed = challenge.challenge();
+    assert_tampering_detected(hasher, &commitment, &revealed, computation);
+}
+
+/// Shared verification logic for [`assert_detects_tampering`], once a commitment and its
+/// revealed randomness have been produced - identical for both the std and `no_std` entry
+/// points above, since from here on everything plays back through a plain [`PlaybackRng`].
+fn assert_tampering_detected<H, E>(
+    hasher: &mut H,
+    commitment: &[u8],
+    revealed: &[u8],
+    computation: impl Fn(&mut dyn CryptoRngCore) -> Result<Vec<u8>, E>,
+) where
+    H: Digest + FixedOutputReset,
+    E: Debug,
+{
+    assert!(
+        !revealed.is_empty(),
+        "computation never drew any randomness - it can't possibly be sensitive to the RNG, \
+         so this harness can't certify it"
+    );
+
+    // The untouched transcript must check out.
+    check_commitment(hasher, commitment, revealed, &[], |rng: &mut PlaybackRng| {
+        computation(rng)
+    })
+    .expect("honest commitment failed to verify against its own revealed randomness");
+
+    // (a) Flipping any single bit of the revealed randomness must be caught. If it isn't,
+    // the computation isn't actually using the randomness it was handed.
+    for byte_index in 0..revealed.len() {
+        for bit in 0..8u8 {
+            let mut tampered_random = revealed.to_vec();
+            tampered_random[byte_index] ^= 1 << bit;
+            let result = check_commitment(hasher, commitment, &tampered_random, &[], |rng: &mut PlaybackRng| {
+                computation(rng)
+            });
+            assert!(
+                matches!(result, Err(Error::VerificationFailed)),
+                "flipping bit {} of revealed random byte {} went undetected - \
+                 does your computation actually use its RNG input?",
+                bit,
+                byte_index
+            );
+        }
+    }
+
+    // (b) Perturbing the recomputed result (independent of the randomness used to produce
+    // it) must also be caught.
+    let result = check_commitment(hasher, commitment, revealed, &[], |rng: &mut PlaybackRng| -> Result<Vec<u8>, E> {
+        let mut result = computation(rng)?;
+        if result.is_empty() {
+            result.push(0);
+        }
+        let last = result.len() - 1;
+        result[last] ^= 1;
+        Ok(result)
+    });
+    assert!(
+        matches!(result, Err(Error::VerificationFailed)),
+        "perturbing the recomputed result went undetected"
+    );
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::{assert_detects_tampering, CryptoRngCore};
+    use sha2::{Digest, Sha256};
+    use std::convert::Infallible;
+
+    #[test]
+    fn detects_tampering_on_an_rng_sensitive_computation() {
+        fn untrusted_computation(rng: &mut dyn CryptoRngCore) -> Result<Vec<u8>, Infallible> {
+            let mut bytes = vec![0; 8];
+            rng.fill_bytes(&mut bytes);
+            Ok(bytes)
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut hasher = Sha256::new();
+        assert_detects_tampering(&mut rng, untrusted_computation, &mut hasher);
+    }
+
+    #[test]
+    #[should_panic]
+    fn catches_a_computation_that_ignores_its_rng() {
+        fn ignores_rng(rng: &mut dyn CryptoRngCore) -> Result<Vec<u8>, Infallible> {
+            // Draws randomness (so there's something to tamper with) but never lets it
+            // influence the output - the bug this harness exists to catch.
+            let mut discarded = [0u8; 8];
+            rng.fill_bytes(&mut discarded);
+            Ok(b"constant".to_vec())
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut hasher = Sha256::new();
+        assert_detects_tampering(&mut rng, ignores_rng, &mut hasher);
+    }
+
+    #[test]
+    #[should_panic]
+    fn catches_a_computation_that_never_touches_its_rng() {
+        fn never_touches_rng(_rng: &mut dyn CryptoRngCore) -> Result<Vec<u8>, Infallible> {
+            Ok(b"constant".to_vec())
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut hasher = Sha256::new();
+        assert_detects_tampering(&mut rng, never_touches_rng, &mut hasher);
+    }
+}
+
+/// `no_std` counterpart of the tests above: the `no_std` [`assert_detects_tampering`] carries
+/// an extra const generic (the recording buffer's capacity `N`) that, unlike the closures in
+/// [`Challenge::new`][crate::Challenge::new], never appears in a parameter type here - `N` has
+/// to be pinned with a turbofish instead of being inferred.
+#[cfg(all(test, not(feature = "std")))]
+mod no_std_tests {
+    use super::{assert_detects_tampering, CryptoRngCore};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use sha2::{Digest, Sha256};
+    use std::convert::Infallible;
+
+    #[test]
+    fn detects_tampering_on_an_rng_sensitive_computation() {
+        fn untrusted_computation(rng: &mut dyn CryptoRngCore) -> Result<Vec<u8>, Infallible> {
+            let mut bytes = vec![0; 8];
+            rng.fill_bytes(&mut bytes);
+            Ok(bytes)
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut hasher = Sha256::new();
+        assert_detects_tampering::<_, _, _, 8>(&mut rng, untrusted_computation, &mut hasher);
+    }
+}